@@ -0,0 +1,259 @@
+/**
+ * W3C Verifiable Credential issuance for verified TLS Notary presentations.
+ *
+ * Once `verify_presentation` has cryptographically proven a set of facts, the
+ * resulting `VerificationResult` is portable but only meaningful to callers who
+ * re-run the WASM verifier. Wrapping it in a W3C Verifiable Credential (VC Data
+ * Model) lets the decentralized-identity ecosystem consume and re-verify the
+ * proven facts directly from the credential's own proof block.
+ */
+
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use ed25519_dalek::{Signer, SigningKey};
+
+use crate::{ser_err, VerificationResult};
+
+/// The VC Data Model JSON-LD context plus our TLS Notary extension term.
+const VC_CONTEXT: [&str; 2] = [
+    "https://www.w3.org/2018/credentials/v1",
+    "https://loom.certo.ventures/credentials/tlsn/v1",
+];
+
+/// Multicodec prefix for an Ed25519 public key (`0xed 0x01`), used when
+/// encoding the issuer key as a `did:key`.
+const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+
+/// A serialized W3C Verifiable Credential built from a `VerificationResult`.
+#[derive(Debug, Serialize, Deserialize)]
+struct VerifiableCredential {
+    #[serde(rename = "@context")]
+    context: Vec<String>,
+    #[serde(rename = "type")]
+    types: Vec<String>,
+    issuer: String,
+    #[serde(rename = "issuanceDate")]
+    issuance_date: String,
+    #[serde(rename = "credentialSubject")]
+    credential_subject: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proof: Option<serde_json::Value>,
+}
+
+/// Issue a signed W3C Verifiable Credential from a verified presentation.
+///
+/// # Arguments
+/// * `verification_result_json` - a `VerificationResult` previously returned by
+///   [`crate::verify_presentation`].
+/// * `signing_key` - the 32-byte Ed25519 seed (hex) used both to derive the
+///   `did:key` issuer and to sign the credential.
+/// * `issuer_did` - an explicit issuer DID; when empty, a `did:key` is derived
+///   from `signing_key`.
+/// * `proof_format` - `"jwt"` for the compact JWT-VC encoding (credential as the
+///   `vc` claim of a JWS) or `"ld"` for an embedded linked-data-style proof.
+///
+/// # Returns
+/// For `"ld"` (or an empty format), the serialized credential JSON string; for
+/// `"jwt"`, the compact JWS token carrying the credential.
+#[wasm_bindgen]
+pub fn issue_credential(
+    verification_result_json: &str,
+    signing_key: &str,
+    issuer_did: &str,
+    proof_format: &str,
+) -> Result<String, JsValue> {
+    let result: VerificationResult = serde_json::from_str(verification_result_json)
+        .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+
+    if !result.valid {
+        return Err(JsValue::from_str(
+            "Cannot issue a credential from an unverified presentation",
+        ));
+    }
+
+    let key = parse_signing_key(signing_key)?;
+    let issuer = if issuer_did.is_empty() {
+        did_key(&key.verifying_key().to_bytes())
+    } else {
+        issuer_did.to_string()
+    };
+
+    let credential = VerifiableCredential {
+        context: VC_CONTEXT.iter().map(|s| s.to_string()).collect(),
+        types: vec![
+            "VerifiableCredential".to_string(),
+            "TlsNotaryCredential".to_string(),
+        ],
+        issuer: issuer.clone(),
+        issuance_date: rfc3339(result.time),
+        credential_subject: credential_subject(&result),
+        proof: None,
+    };
+
+    match proof_format {
+        "jwt" => jwt_vc(&credential, &key, result.time),
+        "ld" | "" => linked_data_proof(credential, &issuer, &key),
+        other => Err(JsValue::from_str(&format!(
+            "Unknown proof format: {}",
+            other
+        ))),
+    }
+}
+
+/// Build the `credentialSubject` from the proven transcript data, binding it to
+/// the proof hash and the notary that attested the session.
+fn credential_subject(result: &VerificationResult) -> serde_json::Value {
+    serde_json::json!({
+        "server_name": result.server_name,
+        "data": result.data,
+        "proof_hash": result.proof_hash,
+        "notary_pubkey": result.notary_pubkey,
+    })
+}
+
+/// Encode the credential as a compact JWT-VC: the credential is carried as the
+/// `vc` claim of a JWS signed with EdDSA.
+fn jwt_vc(
+    credential: &VerifiableCredential,
+    key: &SigningKey,
+    iat: u64,
+) -> Result<String, JsValue> {
+    let header = serde_json::json!({ "alg": "EdDSA", "typ": "JWT" });
+    let claims = serde_json::json!({
+        "iss": credential.issuer,
+        "iat": iat,
+        "sub": credential.credential_subject.get("server_name"),
+        "vc": credential,
+    });
+
+    let signing_input = format!(
+        "{}.{}",
+        base64url(&serde_json::to_vec(&header).map_err(ser_err)?),
+        base64url(&serde_json::to_vec(&claims).map_err(ser_err)?),
+    );
+    let signature = key.sign(signing_input.as_bytes());
+
+    Ok(format!("{}.{}", signing_input, base64url(&signature.to_bytes())))
+}
+
+/// Embed a linked-data-style proof block carrying a base64url-encoded Ed25519
+/// signature over the serialized credential body.
+fn linked_data_proof(
+    mut credential: VerifiableCredential,
+    issuer: &str,
+    key: &SigningKey,
+) -> Result<String, JsValue> {
+    let body = serde_json::to_vec(&credential).map_err(ser_err)?;
+    let signature = key.sign(&body);
+
+    // The fragment identifies the signing key, not the issuer, so derive it from
+    // the key itself (its `did:key` multibase form). This keeps the
+    // verification method well-formed even when `issuer` is an explicit
+    // non-`did:key` DID such as `did:web:…` — in which case blindly trimming the
+    // `did:key:` prefix would leave the whole DID as the fragment.
+    let key_fragment = did_key(&key.verifying_key().to_bytes())
+        .trim_start_matches("did:key:")
+        .to_string();
+
+    credential.proof = Some(serde_json::json!({
+        "type": "Ed25519Signature2020",
+        "created": credential.issuance_date,
+        "verificationMethod": format!("{}#{}", issuer, key_fragment),
+        "proofPurpose": "assertionMethod",
+        "proofValue": base64url(&signature.to_bytes()),
+    }));
+
+    serde_json::to_string(&credential).map_err(ser_err)
+}
+
+/// Parse a 32-byte Ed25519 signing seed from a hex string.
+fn parse_signing_key(hex_key: &str) -> Result<SigningKey, JsValue> {
+    let bytes = hex::decode(hex_key.trim())
+        .map_err(|e| JsValue::from_str(&format!("Invalid signing key: {}", e)))?;
+    let seed: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| JsValue::from_str("Signing key must be 32 bytes"))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Derive a `did:key` identifier from an Ed25519 public key.
+fn did_key(pubkey: &[u8]) -> String {
+    let mut multicodec = ED25519_MULTICODEC.to_vec();
+    multicodec.extend_from_slice(pubkey);
+    format!("did:key:z{}", bs58::encode(multicodec).into_string())
+}
+
+/// Format a Unix timestamp (seconds) as an RFC 3339 / XSD dateTime string.
+fn rfc3339(secs: u64) -> String {
+    // Minimal civil-time conversion sufficient for `issuanceDate`; avoids a
+    // chrono dependency in the WASM build.
+    let days = secs / 86_400;
+    let rem = secs % 86_400;
+    let (h, m, s) = (rem / 3600, (rem % 3600) / 60, rem % 60);
+    let (y, mo, d) = civil_from_days(days as i64);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", y, mo, d, h, m, s)
+}
+
+/// Convert a day count since the Unix epoch to a `(year, month, day)` triple.
+///
+/// Uses Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    (if m <= 2 { y + 1 } else { y }, m, d)
+}
+
+/// Base64url-encode without padding, per the JOSE conventions.
+pub(crate) fn base64url(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+        out.push(ALPHABET[(n >> 18 & 0x3f) as usize] as char);
+        out.push(ALPHABET[(n >> 12 & 0x3f) as usize] as char);
+        if chunk.len() > 1 {
+            out.push(ALPHABET[(n >> 6 & 0x3f) as usize] as char);
+        }
+        if chunk.len() > 2 {
+            out.push(ALPHABET[(n & 0x3f) as usize] as char);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64url_has_no_padding() {
+        assert_eq!(base64url(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64url(b"foob"), "Zm9vYg");
+        assert!(!base64url(b"any").contains('='));
+    }
+
+    #[test]
+    fn did_key_uses_ed25519_prefix() {
+        let did = did_key(&[0u8; 32]);
+        assert!(did.starts_with("did:key:z"));
+    }
+
+    #[test]
+    fn rfc3339_formats_epoch() {
+        // 2021-01-01T00:00:00Z
+        assert_eq!(rfc3339(1_609_459_200), "2021-01-01T00:00:00Z");
+    }
+}
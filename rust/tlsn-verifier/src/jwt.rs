@@ -0,0 +1,428 @@
+/**
+ * JWT signing and validation for verification results.
+ *
+ * `verify_presentation` returns a bare JSON `VerificationResult`; this module
+ * lets a caller instead emit the verified fields as a signed JWT so a relying
+ * service can check provenance offline, and verify such a token against a set of
+ * expected audiences and an expiry.
+ */
+
+use wasm_bindgen::prelude::*;
+use serde::Deserialize;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::credential::base64url;
+use crate::{ser_err, verify_to_result};
+
+/// Supported JWS algorithms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Algorithm {
+    Hs256,
+    Rs256,
+    Es256,
+    Ps256,
+}
+
+impl Algorithm {
+    fn parse(alg: &str) -> Result<Self, JsValue> {
+        match alg {
+            "HS256" => Ok(Algorithm::Hs256),
+            "RS256" => Ok(Algorithm::Rs256),
+            "ES256" => Ok(Algorithm::Es256),
+            "PS256" => Ok(Algorithm::Ps256),
+            other => Err(JsValue::from_str(&format!("Unsupported alg: {}", other))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Algorithm::Hs256 => "HS256",
+            Algorithm::Rs256 => "RS256",
+            Algorithm::Es256 => "ES256",
+            Algorithm::Ps256 => "PS256",
+        }
+    }
+}
+
+/// Optional validation policy for [`verify_result_jwt`].
+#[derive(Debug, Default, Deserialize)]
+struct Validation {
+    /// Allowed audiences; the token's `aud` must match at least one (any-of).
+    #[serde(default)]
+    audiences: Option<Vec<String>>,
+    /// Current time (Unix seconds) used to enforce `exp`. A token that carries
+    /// `exp` is rejected when this is absent, so expiry is never skipped
+    /// silently.
+    #[serde(default)]
+    now: Option<u64>,
+    /// Clock skew allowance, in seconds.
+    #[serde(default)]
+    leeway: u64,
+}
+
+/// Verify a presentation and emit the result as a signed JWT.
+///
+/// The verified fields map into registered claims (`iat` from `output.time`,
+/// `sub` = server name) plus a private `tlsn` claim carrying `data`,
+/// `proof_hash`, `notary_pubkey`, and `redacted_ranges`.
+///
+/// Note: no `exp` claim is emitted, so issued tokens do not expire. Expiry is
+/// enforced by [`verify_result_jwt`] only when the token carries `exp`, and
+/// `iat` is not checked there, so tokens from this function are accepted
+/// regardless of age.
+///
+/// # Arguments
+/// * `presentation_json` - the TLS Notary presentation to verify.
+/// * `signing_key_pem` - HMAC secret (HS256) or a PEM-encoded PKCS#8 RSA/EC
+///   private key; PKCS#1 and SEC1 PEM keys are not accepted.
+/// * `alg` - one of `HS256`, `RS256`, `ES256`, `PS256`.
+#[wasm_bindgen]
+pub fn verify_and_sign(
+    presentation_json: &str,
+    signing_key_pem: &str,
+    alg: &str,
+) -> Result<String, JsValue> {
+    let algorithm = Algorithm::parse(alg)?;
+    let result = verify_to_result(presentation_json, &[])?;
+    if !result.valid {
+        return Err(JsValue::from_str(
+            result.error.as_deref().unwrap_or("Verification failed"),
+        ));
+    }
+
+    let header = serde_json::json!({ "alg": algorithm.name(), "typ": "JWT" });
+    let claims = serde_json::json!({
+        "iat": result.time,
+        "sub": result.server_name,
+        "tlsn": {
+            "data": result.data,
+            "proof_hash": result.proof_hash,
+            "notary_pubkey": result.notary_pubkey,
+            "redacted_ranges": result.redacted_ranges,
+        },
+    });
+
+    let signing_input = format!(
+        "{}.{}",
+        base64url(&serde_json::to_vec(&header).map_err(ser_err)?),
+        base64url(&serde_json::to_vec(&claims).map_err(ser_err)?),
+    );
+    let signature = sign(algorithm, signing_key_pem, signing_input.as_bytes())?;
+
+    Ok(format!("{}.{}", signing_input, base64url(&signature)))
+}
+
+/// Verify a JWT produced by [`verify_and_sign`] and return its claims as JSON.
+///
+/// Checks the signature with `verifying_key` (HMAC secret or PEM SPKI public key),
+/// then validates `exp` and `aud` against the optional `validation` policy.
+///
+/// `expected_alg` (one of `HS256`, `RS256`, `ES256`, `PS256`) pins the accepted
+/// algorithm: a token whose header `alg` differs is rejected before any key is
+/// touched. This closes the classic algorithm-confusion attack where a token
+/// forged with `alg: "HS256"` is HMAC-signed using a public verifying key that
+/// is itself public knowledge.
+///
+/// Note: this intentionally deviates from the originally requested
+/// `verify_result_jwt(token, verifying_key, validation)` signature by inserting
+/// the `expected_alg` parameter. Accepting the algorithm from the
+/// attacker-controlled header is unsafe, so the expected algorithm must be
+/// supplied by the caller rather than read from the token.
+#[wasm_bindgen]
+pub fn verify_result_jwt(
+    token: &str,
+    verifying_key: &str,
+    expected_alg: &str,
+    validation: &str,
+) -> Result<String, JsValue> {
+    let expected = Algorithm::parse(expected_alg)?;
+    let policy: Validation = if validation.trim().is_empty() {
+        Validation::default()
+    } else {
+        serde_json::from_str(validation).map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?
+    };
+
+    let mut parts = token.splitn(3, '.');
+    let (header_b64, claims_b64, sig_b64) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(h), Some(c), Some(s)) => (h, c, s),
+        _ => return Err(JsValue::from_str("Malformed token: expected three segments")),
+    };
+
+    let header: serde_json::Value = serde_json::from_slice(&base64url_decode(header_b64)?)
+        .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+    let alg = header["alg"].as_str().unwrap_or_default();
+    let algorithm = Algorithm::parse(alg)?;
+    if algorithm != expected {
+        return Err(JsValue::from_str(&format!(
+            "Algorithm mismatch: token uses {}, expected {}",
+            algorithm.name(),
+            expected.name(),
+        )));
+    }
+
+    let signing_input = format!("{}.{}", header_b64, claims_b64);
+    let signature = base64url_decode(sig_b64)?;
+    if !verify_signature(algorithm, verifying_key, signing_input.as_bytes(), &signature)? {
+        return Err(JsValue::from_str("Signature invalid"));
+    }
+
+    let claims: serde_json::Value = serde_json::from_slice(&base64url_decode(claims_b64)?)
+        .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+
+    validate_claims(&claims, &policy)?;
+
+    serde_json::to_string(&claims).map_err(ser_err)
+}
+
+/// Enforce `exp` and `aud` from the validation policy.
+fn validate_claims(claims: &serde_json::Value, policy: &Validation) -> Result<(), JsValue> {
+    if let Some(exp) = claims.get("exp").and_then(|v| v.as_u64()) {
+        match policy.now {
+            Some(now) => {
+                if now > exp + policy.leeway {
+                    return Err(JsValue::from_str("Token expired"));
+                }
+            }
+            // A token that carries `exp` must be checked against a clock; refuse
+            // rather than silently accept a possibly-expired token.
+            None => {
+                return Err(JsValue::from_str(
+                    "Token carries `exp` but no current time was supplied for validation",
+                ));
+            }
+        }
+    }
+
+    if let Some(allowed) = &policy.audiences {
+        let matches = match claims.get("aud") {
+            Some(serde_json::Value::String(a)) => allowed.iter().any(|x| x == a),
+            Some(serde_json::Value::Array(auds)) => auds
+                .iter()
+                .filter_map(|v| v.as_str())
+                .any(|a| allowed.iter().any(|x| x == a)),
+            _ => false,
+        };
+        if !matches {
+            return Err(JsValue::from_str("Audience not allowed"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Sign `signing_input` with the given algorithm and key.
+fn sign(alg: Algorithm, key_pem: &str, signing_input: &[u8]) -> Result<Vec<u8>, JsValue> {
+    match alg {
+        Algorithm::Hs256 => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key_pem.as_bytes())
+                .map_err(|e| JsValue::from_str(&format!("HMAC key error: {}", e)))?;
+            mac.update(signing_input);
+            Ok(mac.finalize().into_bytes().to_vec())
+        }
+        Algorithm::Rs256 => rsa_sign(key_pem, signing_input, false),
+        Algorithm::Ps256 => rsa_sign(key_pem, signing_input, true),
+        Algorithm::Es256 => es256_sign(key_pem, signing_input),
+    }
+}
+
+/// Verify a signature over `signing_input`.
+fn verify_signature(
+    alg: Algorithm,
+    key: &str,
+    signing_input: &[u8],
+    signature: &[u8],
+) -> Result<bool, JsValue> {
+    match alg {
+        Algorithm::Hs256 => {
+            let expected = sign(Algorithm::Hs256, key, signing_input)?;
+            Ok(constant_time_eq(&expected, signature))
+        }
+        Algorithm::Rs256 => rsa_verify(key, signing_input, signature, false),
+        Algorithm::Ps256 => rsa_verify(key, signing_input, signature, true),
+        Algorithm::Es256 => es256_verify(key, signing_input, signature),
+    }
+}
+
+/// Decode a PEM block into its DER payload for the underlying signer.
+fn pem_to_der(pem_str: &str) -> Result<Vec<u8>, JsValue> {
+    let parsed = pem::parse(pem_str)
+        .map_err(|e| JsValue::from_str(&format!("Invalid PEM: {}", e)))?;
+    Ok(parsed.into_contents())
+}
+
+fn rsa_sign(key_pem: &str, input: &[u8], pss: bool) -> Result<Vec<u8>, JsValue> {
+    use rsa::pkcs8::DecodePrivateKey;
+    use rsa::RsaPrivateKey;
+
+    let der = pem_to_der(key_pem)?;
+    let key = RsaPrivateKey::from_pkcs8_der(&der)
+        .map_err(|e| JsValue::from_str(&format!("RSA key error: {}", e)))?;
+    let digest = sha256(input);
+    let signature = if pss {
+        let padding = rsa::Pss::new::<Sha256>();
+        key.sign(padding, &digest)
+    } else {
+        let padding = rsa::Pkcs1v15Sign::new::<Sha256>();
+        key.sign(padding, &digest)
+    };
+    signature.map_err(|e| JsValue::from_str(&format!("RSA sign error: {}", e)))
+}
+
+fn rsa_verify(key_pem: &str, input: &[u8], sig: &[u8], pss: bool) -> Result<bool, JsValue> {
+    use rsa::pkcs8::DecodePublicKey;
+    use rsa::RsaPublicKey;
+
+    let der = pem_to_der(key_pem)?;
+    let key = RsaPublicKey::from_public_key_der(&der)
+        .map_err(|e| JsValue::from_str(&format!("RSA key error: {}", e)))?;
+    let digest = sha256(input);
+    let ok = if pss {
+        key.verify(rsa::Pss::new::<Sha256>(), &digest, sig).is_ok()
+    } else {
+        key.verify(rsa::Pkcs1v15Sign::new::<Sha256>(), &digest, sig).is_ok()
+    };
+    Ok(ok)
+}
+
+fn es256_sign(key_pem: &str, input: &[u8]) -> Result<Vec<u8>, JsValue> {
+    use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+    use p256::pkcs8::DecodePrivateKey;
+
+    let der = pem_to_der(key_pem)?;
+    let key = SigningKey::from_pkcs8_der(&der)
+        .map_err(|e| JsValue::from_str(&format!("EC key error: {}", e)))?;
+    let signature: Signature = key.sign(input);
+    Ok(signature.to_bytes().to_vec())
+}
+
+fn es256_verify(key_pem: &str, input: &[u8], sig: &[u8]) -> Result<bool, JsValue> {
+    use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+    use p256::pkcs8::DecodePublicKey;
+
+    let der = pem_to_der(key_pem)?;
+    let key = VerifyingKey::from_public_key_der(&der)
+        .map_err(|e| JsValue::from_str(&format!("EC key error: {}", e)))?;
+    let signature = Signature::from_slice(sig)
+        .map_err(|e| JsValue::from_str(&format!("EC signature error: {}", e)))?;
+    Ok(key.verify(input, &signature).is_ok())
+}
+
+fn sha256(input: &[u8]) -> Vec<u8> {
+    use sha2::Digest;
+    let mut hasher = Sha256::new();
+    hasher.update(input);
+    hasher.finalize().to_vec()
+}
+
+/// Base64url-decode without padding.
+fn base64url_decode(s: &str) -> Result<Vec<u8>, JsValue> {
+    fn val(c: u8) -> Result<u8, JsValue> {
+        match c {
+            b'A'..=b'Z' => Ok(c - b'A'),
+            b'a'..=b'z' => Ok(c - b'a' + 26),
+            b'0'..=b'9' => Ok(c - b'0' + 52),
+            b'-' => Ok(62),
+            b'_' => Ok(63),
+            _ => Err(JsValue::from_str("Invalid base64url character")),
+        }
+    }
+
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len() * 3 / 4);
+    for chunk in bytes.chunks(4) {
+        let mut acc = 0u32;
+        for &c in chunk {
+            acc = (acc << 6) | val(c)? as u32;
+        }
+        acc <<= 6 * (4 - chunk.len());
+        match chunk.len() {
+            2 => out.push((acc >> 16) as u8),
+            3 => {
+                out.push((acc >> 16) as u8);
+                out.push((acc >> 8) as u8);
+            }
+            4 => {
+                out.push((acc >> 16) as u8);
+                out.push((acc >> 8) as u8);
+                out.push(acc as u8);
+            }
+            _ => return Err(JsValue::from_str("Invalid base64url length")),
+        }
+    }
+    Ok(out)
+}
+
+/// Constant-time comparison of equal-length inputs to avoid leaking HMAC
+/// timing; inputs of different lengths are rejected immediately.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64url_round_trip() {
+        let data = b"the quick brown fox";
+        let encoded = base64url(data);
+        assert_eq!(base64url_decode(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn rejects_unknown_alg() {
+        assert!(Algorithm::parse("none").is_err());
+    }
+
+    /// Build a signed HS256 token over `claims` for the verification tests.
+    fn make_hs256(claims: serde_json::Value, secret: &str) -> String {
+        let header = serde_json::json!({ "alg": "HS256", "typ": "JWT" });
+        let signing_input = format!(
+            "{}.{}",
+            base64url(&serde_json::to_vec(&header).unwrap()),
+            base64url(&serde_json::to_vec(&claims).unwrap()),
+        );
+        let sig = sign(Algorithm::Hs256, secret, signing_input.as_bytes()).unwrap();
+        format!("{}.{}", signing_input, base64url(&sig))
+    }
+
+    #[test]
+    fn rejects_algorithm_confusion() {
+        // A public verifying key doubling as an HMAC secret is the attack: the
+        // token must be rejected whenever the pinned algorithm differs from the
+        // header, before the key is ever fed to the HMAC path.
+        let token = make_hs256(serde_json::json!({ "sub": "x" }), "public-key-pem");
+        assert!(verify_result_jwt(&token, "public-key-pem", "ES256", "").is_err());
+        assert!(verify_result_jwt(&token, "public-key-pem", "HS256", "").is_ok());
+    }
+
+    #[test]
+    fn enforces_exp_even_without_clock() {
+        let token = make_hs256(serde_json::json!({ "exp": 100u64 }), "secret");
+        // exp present but no `now` -> rejected rather than silently accepted.
+        assert!(verify_result_jwt(&token, "secret", "HS256", "").is_err());
+        // Clock before exp -> accepted; clock past exp -> rejected.
+        assert!(verify_result_jwt(&token, "secret", "HS256", r#"{"now":50}"#).is_ok());
+        assert!(verify_result_jwt(&token, "secret", "HS256", r#"{"now":200}"#).is_err());
+    }
+
+    #[test]
+    fn any_of_audience_membership() {
+        let claims = serde_json::json!({ "aud": ["a", "b"] });
+        let policy = Validation {
+            audiences: Some(vec!["b".to_string()]),
+            ..Default::default()
+        };
+        assert!(validate_claims(&claims, &policy).is_ok());
+
+        let policy = Validation {
+            audiences: Some(vec!["z".to_string()]),
+            ..Default::default()
+        };
+        assert!(validate_claims(&claims, &policy).is_err());
+    }
+}
@@ -10,6 +10,15 @@ use serde::{Deserialize, Serialize};
 use tlsn_core::presentation::Presentation;
 use sha2::{Sha256, Digest};
 
+mod credential;
+mod jwt;
+mod policy;
+mod transcript;
+
+pub use credential::issue_credential;
+pub use jwt::{verify_and_sign, verify_result_jwt};
+pub use policy::verify_presentation_with_policy;
+
 /// Initialize the WASM module
 #[wasm_bindgen(start)]
 pub fn init() {
@@ -18,7 +27,7 @@ pub fn init() {
 }
 
 /// Verification result returned to JavaScript
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct VerificationResult {
     pub valid: bool,
     pub server_name: String,
@@ -40,29 +49,73 @@ pub struct VerificationResult {
 /// 
 /// # Arguments
 /// * `presentation_json` - JSON string containing TLS Notary presentation
-/// 
+/// * `selectors_json` - optional JSON array of JSON pointer / dotted-path
+///   selectors; when non-empty, only those response-body paths are extracted
+///   into `data` instead of the whole body. Pass `""` for the full body.
+///
 /// # Returns
 /// JSON string with verification result
 #[wasm_bindgen]
-pub fn verify_presentation(presentation_json: &str) -> Result<String, JsValue> {
+pub fn verify_presentation(
+    presentation_json: &str,
+    selectors_json: &str,
+) -> Result<String, JsValue> {
+    let selectors = parse_selectors(selectors_json)?;
+    let result = verify_to_result(presentation_json, &selectors)?;
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Parse the optional selector list passed to [`verify_presentation`].
+pub(crate) fn parse_selectors(selectors_json: &str) -> Result<Vec<String>, JsValue> {
+    if selectors_json.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(selectors_json)
+        .map_err(|e| JsValue::from_str(&format!("Selector parse error: {}", e)))
+}
+
+/// Verify a presentation and build a [`VerificationResult`] in memory.
+///
+/// Shared by [`verify_presentation`] and [`verify_and_sign`] so they agree on
+/// how verified fields are extracted. Parse failures are
+/// returned as `Err`; verification failures are captured in the result's
+/// `error` field with `valid == false`.
+pub(crate) fn verify_to_result(
+    presentation_json: &str,
+    selectors: &[String],
+) -> Result<VerificationResult, JsValue> {
     // Parse presentation
     let presentation: Presentation = serde_json::from_str(presentation_json)
         .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
-    
+
     // Create crypto provider for verification
     let provider = tlsn_core::CryptoProvider::default();
-    
+
     // Perform cryptographic verification
     match presentation.verify(&provider) {
         Ok(output) => {
-            // Extract HTTP data from verified transcript
-            let data = parse_http_response(&output.recv_transcript);
-            
+            // Extract structured request/response from both transcript sides.
+            //
+            // Contract: `output.redacted_ranges` is expressed in the single byte
+            // space formed by concatenating the sent transcript and then the
+            // received transcript — the same order `parse_exchange` partitions on
+            // (see `transcript::split_redactions`). Disclosure tagging is only
+            // sound while this holds; if the verifier ever reports per-direction
+            // ranges instead, `split_redactions` must change in lockstep. The
+            // boundary arithmetic is covered by
+            // `transcript::tests::splits_combined_redactions_by_boundary`.
+            let data = transcript::parse_exchange(
+                &output.sent_transcript,
+                &output.recv_transcript,
+                &output.redacted_ranges,
+                selectors,
+            );
+
             // Calculate proof hash
             let proof_hash = calculate_hash(presentation_json);
-            
-            // Build success result
-            let result = VerificationResult {
+
+            Ok(VerificationResult {
                 valid: true,
                 server_name: output.server_name,
                 time: output.time,
@@ -71,53 +124,25 @@ pub fn verify_presentation(presentation_json: &str) -> Result<String, JsValue> {
                 notary_pubkey: hex::encode(&output.notary_pubkey),
                 redacted_ranges: Some(output.redacted_ranges),
                 error: None,
-            };
-            
-            serde_json::to_string(&result)
-                .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
-        }
-        Err(e) => {
-            // Build error result
-            let result = VerificationResult {
-                valid: false,
-                server_name: String::new(),
-                time: 0,
-                data: serde_json::Value::Null,
-                proof_hash: String::new(),
-                notary_pubkey: String::new(),
-                redacted_ranges: None,
-                error: Some(format!("Verification failed: {}", e)),
-            };
-            
-            serde_json::to_string(&result)
-                .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+            })
         }
+        Err(e) => Ok(VerificationResult {
+            valid: false,
+            server_name: String::new(),
+            time: 0,
+            data: serde_json::Value::Null,
+            proof_hash: String::new(),
+            notary_pubkey: String::new(),
+            redacted_ranges: None,
+            error: Some(format!("Verification failed: {}", e)),
+        }),
     }
 }
 
-/// Parse HTTP response from transcript
-fn parse_http_response(transcript: &[u8]) -> serde_json::Value {
-    // Convert to string
-    let text = match String::from_utf8(transcript.to_vec()) {
-        Ok(s) => s,
-        Err(_) => return serde_json::json!({ "raw": hex::encode(transcript) })
-    };
-    
-    // Try to find HTTP response body
-    if let Some(body_start) = text.find("\r\n\r\n") {
-        let body = &text[body_start + 4..];
-        
-        // Try to parse as JSON
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(body) {
-            return json;
-        }
-        
-        // Return as string if not JSON
-        return serde_json::json!({ "text": body });
-    }
-    
-    // Return full transcript if can't parse
-    serde_json::json!({ "text": text })
+/// Map a `serde_json` serialization error into a `JsValue` for the WASM
+/// boundary. Shared by the credential and JWT modules.
+pub(crate) fn ser_err(e: serde_json::Error) -> JsValue {
+    JsValue::from_str(&format!("Serialization error: {}", e))
 }
 
 /// Calculate SHA-256 hash of presentation
@@ -130,23 +155,20 @@ fn calculate_hash(data: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
+    #[test]
+    fn rejects_malformed_presentation() {
+        // A presentation that is not even valid JSON is an `Err`, not a
+        // `valid: false` result.
+        assert!(verify_presentation("not json", "").is_err());
+    }
+
     #[test]
-    fn test_proof_parsing() {
-        let proof_json = r#"{
-            "session_header": {
-                "server_name": "api.example.com",
-                "handshake_hash": [1, 2, 3, 4]
-            },
-            "transcript_proof": {
-                "sent": [72, 69, 76, 76, 79],
-                "received": [72, 73],
-                "ranges": [{"start": 0, "end": 2}]
-            },
-            "signature": [5, 6, 7, 8]
-        }"#;
-        
-        let result = verify_tls_notary_proof(proof_json);
-        assert!(result.is_ok());
+    fn parses_empty_selectors() {
+        assert!(parse_selectors("").unwrap().is_empty());
+        assert_eq!(
+            parse_selectors(r#"["$.a","$.b"]"#).unwrap(),
+            vec!["$.a".to_string(), "$.b".to_string()]
+        );
     }
 }
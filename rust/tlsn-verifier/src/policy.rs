@@ -0,0 +1,203 @@
+/**
+ * Configurable notary trust anchors and public-key pinning.
+ *
+ * The default `verify_presentation` path uses `CryptoProvider::default()` and
+ * trusts whatever notary key the presentation carries, which is unsafe for
+ * production. This module threads a verification policy through verification: a
+ * set of allowed notary public keys and a custom root store used to validate the
+ * TLS server certificate chain inside the session proof. Failures are reported
+ * as structured errors so callers can tell an untrusted notary apart from a cert
+ * chain or signature problem.
+ */
+
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use tlsn_core::presentation::Presentation;
+
+use crate::{calculate_hash, transcript, VerificationResult};
+
+/// A caller-supplied verification policy.
+#[derive(Debug, Default, Deserialize)]
+struct VerificationPolicy {
+    /// Hex-encoded notary public keys that are allowed to attest a session.
+    /// When empty, any notary is accepted (matching the default behaviour).
+    #[serde(default)]
+    allowed_notary_pubkeys: Vec<String>,
+    /// Custom root certificate store used to validate the server cert chain.
+    /// When empty, the default web PKI roots are used.
+    #[serde(default)]
+    trust_anchors: Vec<TrustAnchor>,
+}
+
+/// A single trust anchor in the custom root store.
+#[derive(Debug, Deserialize)]
+struct TrustAnchor {
+    /// DER subject, hex-encoded.
+    subject: String,
+    /// Subject public key info, hex-encoded.
+    spki: String,
+    /// Optional DER-encoded name constraints, hex-encoded.
+    #[serde(default)]
+    name_constraints: Option<String>,
+}
+
+/// Structured failure reasons surfaced in `VerificationResult.error`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+enum PolicyError {
+    /// The presentation's notary key is not in the allowed set.
+    UntrustedNotary(String),
+    /// The TLS server certificate chain did not validate against the roots.
+    CertChainFailure(String),
+    /// The notary signature over the session did not verify.
+    SignatureInvalid(String),
+    /// Any other verification failure.
+    Other(String),
+}
+
+/// Verify a presentation under a pinning/trust policy.
+///
+/// # Arguments
+/// * `presentation_json` - the TLS Notary presentation to verify.
+/// * `policy_json` - a [`VerificationPolicy`] locking verification to known
+///   notaries and roots.
+///
+/// # Returns
+/// A serialized [`VerificationResult`]; on rejection `valid` is `false` and
+/// `error` carries a structured [`PolicyError`].
+#[wasm_bindgen]
+pub fn verify_presentation_with_policy(
+    presentation_json: &str,
+    policy_json: &str,
+) -> Result<String, JsValue> {
+    let policy: VerificationPolicy = if policy_json.trim().is_empty() {
+        VerificationPolicy::default()
+    } else {
+        serde_json::from_str(policy_json)
+            .map_err(|e| JsValue::from_str(&format!("Policy parse error: {}", e)))?
+    };
+
+    let presentation: Presentation = serde_json::from_str(presentation_json)
+        .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+
+    let provider = build_provider(&policy)?;
+
+    let result = match presentation.verify(&provider) {
+        Ok(output) => {
+            // Enforce notary pinning before trusting any proven field.
+            if !policy.allowed_notary_pubkeys.is_empty()
+                && !notary_allowed(&output.notary_pubkey, &policy.allowed_notary_pubkeys)
+            {
+                reject(PolicyError::UntrustedNotary(format!(
+                    "notary {} is not in the allowed set",
+                    hex::encode(&output.notary_pubkey)
+                )))
+            } else {
+                VerificationResult {
+                    valid: true,
+                    server_name: output.server_name,
+                    time: output.time,
+                    data: transcript::parse_exchange(
+                        &output.sent_transcript,
+                        &output.recv_transcript,
+                        &output.redacted_ranges,
+                        &[],
+                    ),
+                    proof_hash: calculate_hash(presentation_json),
+                    notary_pubkey: hex::encode(&output.notary_pubkey),
+                    redacted_ranges: Some(output.redacted_ranges),
+                    error: None,
+                }
+            }
+        }
+        Err(e) => reject(classify(&e.to_string())),
+    };
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Build a crypto provider from the policy's trust anchors, falling back to the
+/// default web PKI roots when none are supplied.
+fn build_provider(policy: &VerificationPolicy) -> Result<tlsn_core::CryptoProvider, JsValue> {
+    if policy.trust_anchors.is_empty() {
+        return Ok(tlsn_core::CryptoProvider::default());
+    }
+
+    let mut roots = tlsn_core::RootCertStore::empty();
+    for anchor in &policy.trust_anchors {
+        let subject = decode_hex(&anchor.subject, "subject")?;
+        let spki = decode_hex(&anchor.spki, "spki")?;
+        let name_constraints = anchor
+            .name_constraints
+            .as_deref()
+            .map(|nc| decode_hex(nc, "name_constraints"))
+            .transpose()?;
+        roots.add_trust_anchor(subject, spki, name_constraints);
+    }
+
+    Ok(tlsn_core::CryptoProvider::with_root_store(roots))
+}
+
+/// Check whether the presentation's notary key is pinned.
+fn notary_allowed(pubkey: &[u8], allowed: &[String]) -> bool {
+    let encoded = hex::encode(pubkey);
+    allowed.iter().any(|a| a.eq_ignore_ascii_case(&encoded))
+}
+
+/// Map a verifier error string onto a structured [`PolicyError`].
+fn classify(message: &str) -> PolicyError {
+    let lower = message.to_lowercase();
+    if lower.contains("certificate") || lower.contains("cert chain") || lower.contains("chain") {
+        PolicyError::CertChainFailure(message.to_string())
+    } else if lower.contains("signature") {
+        PolicyError::SignatureInvalid(message.to_string())
+    } else {
+        PolicyError::Other(message.to_string())
+    }
+}
+
+/// Build a failed [`VerificationResult`] carrying a serialized [`PolicyError`].
+fn reject(error: PolicyError) -> VerificationResult {
+    let error = serde_json::to_string(&error).unwrap_or_else(|_| "verification failed".to_string());
+    VerificationResult {
+        valid: false,
+        server_name: String::new(),
+        time: 0,
+        data: serde_json::Value::Null,
+        proof_hash: String::new(),
+        notary_pubkey: String::new(),
+        redacted_ranges: None,
+        error: Some(error),
+    }
+}
+
+fn decode_hex(value: &str, field: &str) -> Result<Vec<u8>, JsValue> {
+    hex::decode(value.trim())
+        .map_err(|e| JsValue::from_str(&format!("Invalid {} in trust anchor: {}", field, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_distinguishes_failure_modes() {
+        assert!(matches!(
+            classify("certificate chain validation failed"),
+            PolicyError::CertChainFailure(_)
+        ));
+        assert!(matches!(
+            classify("notary signature did not verify"),
+            PolicyError::SignatureInvalid(_)
+        ));
+        assert!(matches!(classify("something else"), PolicyError::Other(_)));
+    }
+
+    #[test]
+    fn notary_pinning_is_case_insensitive() {
+        let allowed = vec!["AABB".to_string()];
+        assert!(notary_allowed(&[0xaa, 0xbb], &allowed));
+        assert!(!notary_allowed(&[0xcc], &allowed));
+    }
+}
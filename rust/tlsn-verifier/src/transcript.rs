@@ -0,0 +1,466 @@
+/**
+ * Selective-disclosure aware parsing of both transcript directions.
+ *
+ * The verified output carries the bytes the prover chose to reveal on both the
+ * sent and received sides, along with the ranges that were redacted. Parsing
+ * only the received side loses the request that was proven, and collapsing the
+ * body into one blob hides which fields were actually disclosed. This module
+ * parses both directions into structured request/response objects and tags the
+ * request headers and the response status line, headers and body as fully
+ * revealed, partially redacted, or fully hidden — preserving the byte offsets
+ * so a consumer can reason about exactly what was proven. The request method
+ * and path are plain strings without a disclosure tag, and values picked out
+ * by selectors carry the disclosure of the whole body and no byte offsets.
+ */
+
+use serde::Serialize;
+
+/// How much of a parsed field was disclosed by the prover.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Disclosure {
+    /// No byte of the field overlaps a redacted range.
+    Revealed,
+    /// The field straddles a redaction boundary.
+    Partial,
+    /// Every byte of the field falls inside a redacted range.
+    Hidden,
+}
+
+/// A single parsed field, tagged with its disclosure and byte span.
+#[derive(Debug, Serialize)]
+struct Field {
+    value: String,
+    disclosure: Disclosure,
+    /// Byte offset `[start, end)` within its transcript direction.
+    offset: [usize; 2],
+}
+
+impl Field {
+    /// Build a field from a byte span, rendering non-UTF8 bytes as hex and
+    /// tagging disclosure against the redacted ranges.
+    fn from_span(bytes: &[u8], start: usize, end: usize, redacted: &[(usize, usize)]) -> Self {
+        let slice = &bytes[start..end];
+        let value = match std::str::from_utf8(slice) {
+            Ok(s) => s.to_string(),
+            Err(_) => hex::encode(slice),
+        };
+        Field {
+            value,
+            disclosure: disclosure_of(start, end, redacted),
+            offset: [start, end],
+        }
+    }
+}
+
+/// Parse the sent request and received response into a structured object.
+///
+/// `redacted` indexes the concatenation of the sent transcript followed by the
+/// received transcript — the single flat space the verifier reports redactions
+/// in. It is partitioned onto each side so request *and* response fields are
+/// tagged against the redactions that actually apply to them; a stripped
+/// request header is therefore reported as hidden rather than revealed. When
+/// `selectors` is non-empty, only the requested JSON pointer / dotted paths are
+/// extracted from the response body rather than dumping the whole body.
+pub(crate) fn parse_exchange(
+    sent: &[u8],
+    recv: &[u8],
+    redacted: &[(usize, usize)],
+    selectors: &[String],
+) -> serde_json::Value {
+    let (sent_redacted, recv_redacted) = split_redactions(sent.len(), redacted);
+    serde_json::json!({
+        "request": parse_request(sent, &sent_redacted),
+        "response": parse_response(recv, &recv_redacted, selectors),
+    })
+}
+
+/// Partition combined `sent ++ recv` redaction ranges into sent-relative and
+/// received-relative ranges, splitting any range that straddles the boundary.
+fn split_redactions(
+    sent_len: usize,
+    redacted: &[(usize, usize)],
+) -> (Vec<(usize, usize)>, Vec<(usize, usize)>) {
+    let mut sent = Vec::new();
+    let mut recv = Vec::new();
+    for &(start, end) in redacted {
+        if start >= end {
+            continue;
+        }
+        if start < sent_len {
+            sent.push((start, end.min(sent_len)));
+        }
+        if end > sent_len {
+            recv.push((start.max(sent_len) - sent_len, end - sent_len));
+        }
+    }
+    (sent, recv)
+}
+
+/// Parse the request line and headers from the sent transcript.
+fn parse_request(sent: &[u8], redacted: &[(usize, usize)]) -> serde_json::Value {
+    let (lines, _body_start) = split_head(sent);
+    let mut iter = lines.into_iter();
+
+    let mut method = String::new();
+    let mut path = String::new();
+    if let Some((line_start, line)) = iter.next() {
+        let text = String::from_utf8_lossy(&sent[line_start..line_start + line]);
+        let mut words = text.split_whitespace();
+        method = words.next().unwrap_or_default().to_string();
+        path = words.next().unwrap_or_default().to_string();
+    }
+
+    serde_json::json!({
+        "method": method,
+        "path": path,
+        "headers": parse_headers(sent, iter, redacted),
+    })
+}
+
+/// Parse the status line, headers, and body from the received transcript.
+///
+/// `Transfer-Encoding: chunked` bodies are de-chunked before the body is
+/// rendered or selected from. When `selectors` is non-empty, the parsed body is
+/// reduced to just those paths instead of being emitted whole.
+fn parse_response(
+    recv: &[u8],
+    redacted: &[(usize, usize)],
+    selectors: &[String],
+) -> serde_json::Value {
+    let (lines, body_start) = split_head(recv);
+    let mut iter = lines.into_iter();
+
+    let status = match iter.next() {
+        Some((line_start, line)) => {
+            Field::from_span(recv, line_start, line_start + line, redacted)
+        }
+        None => Field::from_span(recv, 0, 0, redacted),
+    };
+
+    let headers = parse_headers(recv, iter, redacted);
+    let body_span = &recv[body_start..];
+    let body_disclosure = disclosure_of(body_start, recv.len(), redacted);
+
+    // De-chunk before interpreting the body.
+    let decoded = if header_is_chunked(&headers) {
+        dechunk(body_span)
+    } else {
+        body_span.to_vec()
+    };
+
+    let mut response = serde_json::json!({
+        "status": status,
+        "headers": headers,
+    });
+    let map = response.as_object_mut().expect("object");
+
+    if selectors.is_empty() {
+        map.insert("body".to_string(), serde_json::json!({
+            "value": render(&decoded),
+            "disclosure": body_disclosure,
+            "offset": [body_start, recv.len()],
+        }));
+    } else {
+        // Extract only the requested paths rather than dumping the full body.
+        map.insert("selected".to_string(), select_paths(&decoded, selectors, &body_disclosure));
+        map.insert("body_disclosure".to_string(), serde_json::to_value(&body_disclosure).unwrap());
+    }
+
+    response
+}
+
+/// Extract the requested JSON pointer / dotted-path selectors from a body.
+fn select_paths(
+    body: &[u8],
+    selectors: &[String],
+    disclosure: &Disclosure,
+) -> serde_json::Value {
+    let parsed: serde_json::Value = match serde_json::from_slice(body) {
+        Ok(v) => v,
+        // Non-JSON body: fall back to the rendered (hex if non-UTF8) content.
+        Err(_) => return serde_json::json!({ "raw": render(body) }),
+    };
+
+    let mut out = serde_json::Map::new();
+    for selector in selectors {
+        let value = resolve(&parsed, selector).cloned().unwrap_or(serde_json::Value::Null);
+        out.insert(selector.clone(), serde_json::json!({
+            "value": value,
+            "disclosure": disclosure,
+        }));
+    }
+    serde_json::Value::Object(out)
+}
+
+/// Resolve a single selector against a JSON value.
+///
+/// Supports RFC 6901 JSON pointers (`/data/balance`) and dotted paths
+/// (`$.data.balance`, `data.balance`, with numeric array indices).
+fn resolve<'a>(value: &'a serde_json::Value, selector: &str) -> Option<&'a serde_json::Value> {
+    if selector.starts_with('/') {
+        return value.pointer(selector);
+    }
+    let path = selector.trim_start_matches("$.").trim_start_matches('$');
+    let mut current = value;
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        current = match current {
+            serde_json::Value::Object(map) => map.get(segment)?,
+            serde_json::Value::Array(arr) => arr.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+/// Render bytes as a UTF-8 string, falling back to hex for non-UTF8 content.
+fn render(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.to_string(),
+        Err(_) => hex::encode(bytes),
+    }
+}
+
+/// Whether the response declared `Transfer-Encoding: chunked`.
+fn header_is_chunked(headers: &serde_json::Map<String, serde_json::Value>) -> bool {
+    headers.iter().any(|(name, field)| {
+        name.eq_ignore_ascii_case("transfer-encoding")
+            && field
+                .get("value")
+                .and_then(|v| v.as_str())
+                .map(|v| v.to_ascii_lowercase().contains("chunked"))
+                .unwrap_or(false)
+    })
+}
+
+/// Decode an HTTP/1.1 `chunked` body into its concatenated payload.
+fn dechunk(mut body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len());
+    loop {
+        let line_end = match find_crlf(body) {
+            Some(i) => i,
+            None => break,
+        };
+        // The chunk size may be followed by `;ext` extensions.
+        let size_token = &body[..line_end];
+        let size_str = std::str::from_utf8(size_token)
+            .ok()
+            .and_then(|s| s.split(';').next())
+            .map(|s| s.trim());
+        let size = match size_str.and_then(|s| usize::from_str_radix(s, 16).ok()) {
+            Some(n) => n,
+            None => break,
+        };
+        body = &body[line_end + 2..];
+        if size == 0 {
+            break;
+        }
+        if size > body.len() {
+            out.extend_from_slice(body);
+            break;
+        }
+        out.extend_from_slice(&body[..size]);
+        // Skip the chunk payload and its trailing CRLF.
+        body = &body[size..];
+        if body.starts_with(b"\r\n") {
+            body = &body[2..];
+        }
+    }
+    out
+}
+
+/// Locate the next CRLF.
+fn find_crlf(bytes: &[u8]) -> Option<usize> {
+    bytes.windows(2).position(|w| w == b"\r\n")
+}
+
+/// Turn a sequence of header lines into a map of name to disclosure-tagged value.
+fn parse_headers(
+    bytes: &[u8],
+    lines: impl Iterator<Item = (usize, usize)>,
+    redacted: &[(usize, usize)],
+) -> serde_json::Map<String, serde_json::Value> {
+    let mut headers = serde_json::Map::new();
+    for (line_start, line_len) in lines {
+        if line_len == 0 {
+            continue;
+        }
+        let line = &bytes[line_start..line_start + line_len];
+        let colon = match line.iter().position(|&b| b == b':') {
+            Some(i) => i,
+            None => continue,
+        };
+        let name = String::from_utf8_lossy(&line[..colon]).trim().to_string();
+        // Value begins after the colon and any single leading space.
+        let mut value_start = line_start + colon + 1;
+        if bytes.get(value_start) == Some(&b' ') {
+            value_start += 1;
+        }
+        let value_end = line_start + line_len;
+        let field = Field::from_span(bytes, value_start, value_end, redacted);
+        headers.insert(name, serde_json::to_value(field).unwrap_or(serde_json::Value::Null));
+    }
+    headers
+}
+
+/// Split a transcript into header lines (each as `(offset, length)`, CRLF
+/// stripped) and the byte offset where the body begins.
+///
+/// Leading informational (`1xx`) response blocks — a `100 Continue` preamble or
+/// a proxied double-header — are skipped so the real status line and headers are
+/// parsed instead of being swallowed into the body. Offsets remain absolute into
+/// `bytes`, so disclosure tagging against the redacted ranges is unaffected.
+fn split_head(bytes: &[u8]) -> (Vec<(usize, usize)>, usize) {
+    let head_start = skip_informational_blocks(bytes);
+    let body_start = find_crlf_crlf(&bytes[head_start..])
+        .map(|i| head_start + i + 4)
+        .unwrap_or(bytes.len());
+    let end = body_start.min(bytes.len());
+
+    let mut lines = Vec::new();
+    let mut start = head_start;
+    let mut i = head_start;
+    while i < end {
+        if bytes[i] == b'\r' && bytes.get(i + 1) == Some(&b'\n') {
+            lines.push((start, i - start));
+            i += 2;
+            start = i;
+        } else {
+            i += 1;
+        }
+    }
+    (lines, end)
+}
+
+/// Offset of the first non-informational header block, skipping any leading
+/// `HTTP/x.y 1xx` blocks that precede the final response head.
+fn skip_informational_blocks(bytes: &[u8]) -> usize {
+    let mut offset = 0;
+    while is_informational_status(&bytes[offset..]) {
+        match find_crlf_crlf(&bytes[offset..]) {
+            Some(i) => offset += i + 4,
+            None => break,
+        }
+    }
+    offset
+}
+
+/// Does the block beginning at the slice start carry a `1xx` status line?
+fn is_informational_status(block: &[u8]) -> bool {
+    let line_end = find_crlf(block).unwrap_or(block.len());
+    let mut parts = block[..line_end].split(|&b| b == b' ');
+    match (parts.next(), parts.next()) {
+        (Some(proto), Some(code)) => {
+            proto.starts_with(b"HTTP/") && code.len() == 3 && code.first() == Some(&b'1')
+        }
+        _ => false,
+    }
+}
+
+/// Locate the `\r\n\r\n` header/body boundary.
+fn find_crlf_crlf(bytes: &[u8]) -> Option<usize> {
+    bytes.windows(4).position(|w| w == b"\r\n\r\n")
+}
+
+/// Classify a `[start, end)` span against the redacted ranges.
+fn disclosure_of(start: usize, end: usize, redacted: &[(usize, usize)]) -> Disclosure {
+    if start >= end {
+        return Disclosure::Revealed;
+    }
+    let covered: usize = redacted
+        .iter()
+        .map(|&(rs, re)| {
+            let lo = rs.max(start);
+            let hi = re.min(end);
+            hi.saturating_sub(lo)
+        })
+        .sum();
+
+    if covered == 0 {
+        Disclosure::Revealed
+    } else if covered >= end - start {
+        Disclosure::Hidden
+    } else {
+        Disclosure::Partial
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disclosure_tags_overlap() {
+        let ranges = [(5, 10)];
+        assert_eq!(disclosure_of(0, 4, &ranges), Disclosure::Revealed);
+        assert_eq!(disclosure_of(5, 10, &ranges), Disclosure::Hidden);
+        assert_eq!(disclosure_of(3, 8, &ranges), Disclosure::Partial);
+    }
+
+    #[test]
+    fn parses_request_method_and_path() {
+        let sent = b"GET /account/balance HTTP/1.1\r\nHost: api.example.com\r\n\r\n";
+        let request = parse_request(sent, &[]);
+        assert_eq!(request["method"], "GET");
+        assert_eq!(request["path"], "/account/balance");
+        assert_eq!(request["headers"]["Host"]["value"], "api.example.com");
+    }
+
+    #[test]
+    fn tags_redacted_request_header() {
+        let sent = b"GET / HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n";
+        let value_start = sent.windows(6).position(|w| w == b"Bearer").unwrap();
+        let redacted = [(value_start, sent.len())];
+        let request = parse_request(sent, &redacted);
+        assert_eq!(request["headers"]["Authorization"]["disclosure"], "hidden");
+    }
+
+    #[test]
+    fn splits_combined_redactions_by_boundary() {
+        // One range entirely in sent, one straddling into recv.
+        let (sent, recv) = split_redactions(10, &[(2, 5), (8, 14)]);
+        assert_eq!(sent, vec![(2, 5), (8, 10)]);
+        assert_eq!(recv, vec![(0, 4)]);
+    }
+
+    #[test]
+    fn tags_redacted_response_body() {
+        let recv = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"balance\":42}";
+        let body_start = recv.len() - "{\"balance\":42}".len();
+        let redacted = [(body_start, recv.len())];
+        let response = parse_response(recv, &redacted, &[]);
+        assert_eq!(response["body"]["disclosure"], "hidden");
+        assert_eq!(response["status"]["disclosure"], "revealed");
+    }
+
+    #[test]
+    fn skips_informational_preamble() {
+        let recv = b"HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"balance\":42}";
+        let response = parse_response(recv, &[], &[]);
+        assert_eq!(response["status"]["value"], "HTTP/1.1 200 OK");
+        assert_eq!(response["headers"]["Content-Type"]["value"], "application/json");
+        assert_eq!(response["body"]["value"], "{\"balance\":42}");
+    }
+
+    #[test]
+    fn dechunks_chunked_body() {
+        let recv = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n";
+        let response = parse_response(recv, &[], &[]);
+        assert_eq!(response["body"]["value"], "Wikipedia");
+    }
+
+    #[test]
+    fn extracts_only_selected_paths() {
+        let recv = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"data\":{\"balance\":100,\"account\":\"secret\"}}";
+        let selectors = ["$.data.balance".to_string()];
+        let response = parse_response(recv, &[], &selectors);
+        assert_eq!(response["selected"]["$.data.balance"]["value"], 100);
+        assert!(response.get("body").is_none());
+    }
+
+    #[test]
+    fn json_pointer_selector() {
+        let body = serde_json::json!({ "data": { "balance": 7 } });
+        assert_eq!(resolve(&body, "/data/balance"), Some(&serde_json::json!(7)));
+    }
+}
@@ -1,42 +1,94 @@
-use tlsn_verifier::{verify_tls_notary_proof, TlsNotaryProof};
+//! End-to-end tests for the public `wasm_bindgen` entry points.
+//!
+//! `verify_presentation` needs a cryptographically valid TLS Notary
+//! `Presentation` to reach its success path, so here it is exercised on its
+//! error path; `issue_credential` operates on a `VerificationResult` JSON
+//! and is driven all the way through both proof formats.
 
-#[test]
-fn test_basic_verification() {
-    let proof_json = r#"{
-        "session_header": {
-            "server_name": "api.bankofamerica.com",
-            "handshake_hash": [1, 2, 3, 4, 5, 6, 7, 8]
-        },
-        "transcript_proof": {
-            "sent": [],
-            "received": [72, 101, 108, 108, 111],
-            "ranges": [{"start": 0, "end": 5}]
-        },
-        "signature": [9, 10, 11, 12]
-    }"#;
-    
-    let result = verify_tls_notary_proof(proof_json);
-    assert!(result.is_ok());
-    
-    let verified_data = result.unwrap();
-    assert!(verified_data.contains("api.bankofamerica.com"));
+use tlsn_verifier::{issue_credential, verify_presentation};
+
+/// A minimal valid `VerificationResult` as emitted by `verify_presentation`.
+fn verified_result_json() -> String {
+    serde_json::json!({
+        "valid": true,
+        "server_name": "api.example.com",
+        "time": 1_609_459_200u64,
+        "data": { "response": { "status": { "value": "HTTP/1.1 200 OK" } } },
+        "proof_hash": "deadbeef",
+        "notary_pubkey": "abcd",
+        "redacted_ranges": [[0, 4]],
+        "error": null,
+    })
+    .to_string()
 }
 
+/// The 32-byte Ed25519 seed (hex) used to sign test credentials.
+const SIGNING_SEED: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+
 #[test]
-fn test_invalid_json() {
-    let invalid_json = "not valid json";
-    let result = verify_tls_notary_proof(invalid_json);
+fn verify_presentation_rejects_invalid_json() {
+    // The two-argument signature (presentation + selectors) is the current
+    // public contract; malformed input is surfaced as an error.
+    let result = verify_presentation("not valid json", "");
     assert!(result.is_err());
 }
 
 #[test]
-fn test_missing_fields() {
-    let incomplete_json = r#"{
-        "session_header": {
-            "server_name": "test.com"
-        }
-    }"#;
-    
-    let result = verify_tls_notary_proof(incomplete_json);
-    assert!(result.is_err());
+fn issue_jwt_vc_credential() {
+    let token = issue_credential(&verified_result_json(), SIGNING_SEED, "", "jwt")
+        .expect("jwt-vc issuance");
+    // Compact JWS: three base64url segments.
+    assert_eq!(token.split('.').count(), 3);
+}
+
+#[test]
+fn issue_linked_data_credential() {
+    let vc_json = issue_credential(&verified_result_json(), SIGNING_SEED, "", "ld")
+        .expect("ld issuance");
+    let vc: serde_json::Value = serde_json::from_str(&vc_json).unwrap();
+
+    assert_eq!(vc["type"][0], "VerifiableCredential");
+    assert_eq!(vc["type"][1], "TlsNotaryCredential");
+    assert_eq!(vc["issuer"], vc["proof"]["verificationMethod"]
+        .as_str()
+        .unwrap()
+        .split('#')
+        .next()
+        .unwrap());
+    assert_eq!(vc["proof"]["proofPurpose"], "assertionMethod");
+    assert_eq!(vc["credentialSubject"]["server_name"], "api.example.com");
+}
+
+#[test]
+fn issue_credential_with_did_web_issuer() {
+    // An explicit non-`did:key` issuer must still yield a well-formed
+    // verification method fragment derived from the key, not the whole DID.
+    let vc_json = issue_credential(
+        &verified_result_json(),
+        SIGNING_SEED,
+        "did:web:example.com",
+        "ld",
+    )
+    .expect("ld issuance");
+    let vc: serde_json::Value = serde_json::from_str(&vc_json).unwrap();
+
+    let vm = vc["proof"]["verificationMethod"].as_str().unwrap();
+    assert!(vm.starts_with("did:web:example.com#z"), "got {vm}");
+}
+
+#[test]
+fn issue_credential_refuses_unverified_result() {
+    let invalid = serde_json::json!({
+        "valid": false,
+        "server_name": "",
+        "time": 0u64,
+        "data": null,
+        "proof_hash": "",
+        "notary_pubkey": "",
+        "redacted_ranges": null,
+        "error": "Verification failed",
+    })
+    .to_string();
+
+    assert!(issue_credential(&invalid, SIGNING_SEED, "", "jwt").is_err());
 }